@@ -1,3 +1,97 @@
+mod entities;
+
+use std::borrow::Cow;
+
+/// Whether `b` is allowed in the local (before `@`) part of a CommonMark email autolink.
+fn is_email_local_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric()
+        || matches!(
+            b,
+            b'.' | b'!'
+                | b'#'
+                | b'$'
+                | b'%'
+                | b'&'
+                | b'\''
+                | b'*'
+                | b'+'
+                | b'/'
+                | b'='
+                | b'?'
+                | b'^'
+                | b'_'
+                | b'`'
+                | b'{'
+                | b'|'
+                | b'}'
+                | b'~'
+                | b'-'
+        )
+}
+
+/// Decode a numeric character reference's code point value, clamping null, surrogates,
+/// and anything past `U+10FFFF` to the replacement character as CommonMark requires.
+fn decode_code_point(value: u32) -> char {
+    match value {
+        0 | 0xD800..=0xDFFF => '\u{FFFD}',
+        _ => char::from_u32(value).unwrap_or('\u{FFFD}'),
+    }
+}
+
+/// The column alignment of a GFM table column, as declared by its delimiter-row cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Alignment {
+    None,
+    Left,
+    Center,
+    Right,
+}
+
+/// Distinguishes the two forms a CommonMark autolink can take, since an email autolink's
+/// destination needs a `mailto:` prefix the renderer must add that a URI autolink doesn't.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LinkType {
+    Autolink,
+    Email,
+}
+
+/// Tag names HTML-block start condition 6 recognizes, i.e. block-level elements that may
+/// interrupt a paragraph. Taken from the CommonMark spec's fixed list.
+const HTML_BLOCK_TAGS: &[&str] = &[
+    "address", "article", "aside", "base", "basefont", "blockquote", "body", "caption",
+    "center", "col", "colgroup", "dd", "details", "dialog", "dir", "div", "dl", "dt",
+    "fieldset", "figcaption", "figure", "footer", "form", "frame", "frameset", "h1", "h2",
+    "h3", "h4", "h5", "h6", "head", "header", "hr", "html", "iframe", "legend", "li",
+    "link", "main", "menu", "menuitem", "nav", "noframes", "ol", "optgroup", "option", "p",
+    "param", "section", "summary", "table", "tbody", "td", "tfoot", "th", "thead", "title",
+    "tr", "track", "ul",
+];
+
+fn is_html_block_tag(name: &[u8]) -> bool {
+    HTML_BLOCK_TAGS
+        .iter()
+        .any(|tag| name.eq_ignore_ascii_case(tag.as_bytes()))
+}
+
+/// Remembers the offset at which `scan_html_block_start` last failed, so that a repeated
+/// scan starting from that same offset (as happens when a block scanner retries several
+/// start conditions against the same line) doesn't redo the same failing tag-grammar walk.
+/// Mirrors pulldown-cmark's `HtmlScanGuard`.
+#[derive(Clone, Copy, Debug, Default)]
+struct HtmlScanGuard {
+    failed_at: Option<usize>,
+}
+
+impl HtmlScanGuard {
+    fn already_failed(&self, offset: usize) -> bool {
+        self.failed_at == Some(offset)
+    }
+
+    fn record_failure(&mut self, offset: usize) {
+        self.failed_at = Some(offset);
+    }
+}
+
 #[derive(Clone)]
 pub struct LineStart<'a> {
     bytes: &'a [u8],
@@ -5,6 +99,7 @@ pub struct LineStart<'a> {
     tab_start: usize,
     spaces_remaining: usize,
     min_hrule_offset: usize,
+    html_scan_guard: HtmlScanGuard,
 }
 
 impl<'a> LineStart<'a> {
@@ -15,6 +110,7 @@ impl<'a> LineStart<'a> {
             tab_start: 0,
             spaces_remaining: 0,
             min_hrule_offset: 0,
+            html_scan_guard: HtmlScanGuard::default(),
         }
     }
 
@@ -23,7 +119,7 @@ impl<'a> LineStart<'a> {
     }
 
     pub fn peek(&self) -> Option<u8> {
-        self.bytes.last().copied()
+        self.bytes.get(self.cur).copied()
     }
 
     pub fn next(&mut self) -> u8 {
@@ -47,13 +143,13 @@ impl<'a> LineStart<'a> {
     }
 
     pub fn scan_space(&mut self, mut n: usize) -> usize {
+        let n_requested = n;
+
         let x = self.spaces_remaining.min(n);
 
         n -= x;
         self.spaces_remaining -= x;
 
-        let n_save = 0;
-
         while n > 0 {
             match self.peek() {
                 Some(b' ') => {
@@ -68,13 +164,13 @@ impl<'a> LineStart<'a> {
                     self.spaces_remaining = spaces - x;
 
                     self.cur += 1;
-                    self.tab_start += self.cur;
+                    self.tab_start = self.cur;
                 }
                 _ => break,
             }
         }
 
-        n_save - n
+        n_requested - n
     }
 
     pub fn scan_ch(&mut self, ch: u8) -> bool {
@@ -100,6 +196,599 @@ impl<'a> LineStart<'a> {
         .unwrap_or(false)
     }
 
+    /// Scan the opening fence of a fenced code block.
+    ///
+    /// After up to three leading spaces (whose width is returned so it can be stripped
+    /// from each line of the block's content), requires a run of at least three identical
+    /// `` ` `` or `~` characters. For backtick fences the remainder of the line (the info
+    /// string) must not itself contain a backtick, since that would instead close an
+    /// inline code span; tilde fences have no such restriction. Returns the fence
+    /// character, its length, and the indentation on success.
+    pub fn scan_code_fence(&mut self) -> Option<(u8, usize, usize)> {
+        self.try_scan(|this| {
+            let indent = this.scan_space(3);
+
+            let fence_char = match this.peek() {
+                Some(c) if matches!(c, b'`' | b'~') => c,
+                _ => return Err(()),
+            };
+
+            let mut fence_len = 0;
+
+            while this.peek() == Some(fence_char) {
+                this.cur += 1;
+                fence_len += 1;
+            }
+
+            if fence_len < 3 {
+                return Err(());
+            }
+
+            if fence_char == b'`' {
+                let rest = &this.bytes[this.cur..];
+                let info_end = rest
+                    .iter()
+                    .position(|&b| matches!(b, b'\r' | b'\n'))
+                    .unwrap_or(rest.len());
+
+                if rest[..info_end].contains(&b'`') {
+                    return Err(());
+                }
+            }
+
+            Ok((fence_char, fence_len, indent))
+        })
+        .ok()
+    }
+
+    /// Scan the closing fence of a fenced code block opened with `fence_char` repeated
+    /// `fence_len` times.
+    ///
+    /// A closing fence allows up to three leading spaces, then a run of `fence_char` at
+    /// least as long as the opening fence, then nothing but trailing spaces/tabs up to
+    /// EOL.
+    pub fn scan_closing_fence(&mut self, fence_char: u8, fence_len: usize) -> bool {
+        self.try_scan(|this| {
+            this.scan_space(3);
+
+            let mut len = 0;
+
+            while this.peek() == Some(fence_char) {
+                this.cur += 1;
+                len += 1;
+            }
+
+            if len < fence_len {
+                return Err(());
+            }
+
+            this.skip_spaces();
+
+            if this.is_at_eol() {
+                Ok(())
+            } else {
+                Err(())
+            }
+        })
+        .is_ok()
+    }
+
+    /// Scan an ATX heading opener (`#` through `######`).
+    ///
+    /// After up to three leading spaces, requires a run of 1–6 `#` characters terminated
+    /// by a space/tab or EOL; a bare `#text` with no separating whitespace is not a
+    /// heading. The returned level is the number of `#` characters; trimming the optional
+    /// trailing `#...` closing sequence from the heading content is left to the caller.
+    pub fn scan_atx_heading(&mut self) -> Option<usize> {
+        self.try_scan(|this| {
+            this.scan_space(3);
+
+            let mut level = 0;
+
+            while this.peek() == Some(b'#') {
+                this.cur += 1;
+                level += 1;
+            }
+
+            if level == 0 || level > 6 {
+                return Err(());
+            }
+
+            if this.scan_space(1) == 1 || this.is_at_eol() {
+                Ok(level)
+            } else {
+                Err(())
+            }
+        })
+        .ok()
+    }
+
+    /// Scan a setext heading underline.
+    ///
+    /// After up to three leading spaces, the rest of the line (save for trailing
+    /// whitespace) must consist solely of `=` (level 1) or `-` (level 2) characters.
+    /// Wrapped in `try_scan` so that a `-` line which is not a pure underline (such as
+    /// `- - -` or `- item`) is left untouched for the hrule and list-marker scanners to
+    /// consider instead when this fails.
+    pub fn scan_setext_underline(&mut self) -> Option<usize> {
+        self.try_scan(|this| {
+            this.scan_space(3);
+
+            let ch = match this.peek() {
+                Some(c) if matches!(c, b'=' | b'-') => c,
+                _ => return Err(()),
+            };
+
+            while this.peek() == Some(ch) {
+                this.cur += 1;
+            }
+
+            this.skip_spaces();
+
+            if !this.is_at_eol() {
+                return Err(());
+            }
+
+            Ok(if ch == b'=' { 1 } else { 2 })
+        })
+        .ok()
+    }
+
+    /// Scan a GFM table delimiter row, e.g. `| :--- | :--: | ---: |`.
+    ///
+    /// Splits the line on `|` into cells (leading/trailing pipes are optional) and, for
+    /// each cell, requires at least one `-` optionally bracketed by a leading and/or
+    /// trailing `:`: leading-only is `Left`, trailing-only is `Right`, both is `Center`,
+    /// neither is `None`. An empty cell, or one containing anything but `:`, `-`, and
+    /// spaces, fails the whole scan so the preceding line is left as an ordinary
+    /// paragraph.
+    pub fn scan_table_delimiter_row(&mut self) -> Option<Vec<Alignment>> {
+        self.try_scan(|this| {
+            this.scan_space(3);
+            this.skip_spaces();
+            this.scan_ch(b'|');
+
+            let mut alignments = Vec::new();
+
+            loop {
+                this.skip_spaces();
+
+                let leading_colon = this.scan_ch(b':');
+                let mut dash_count = 0;
+
+                while this.peek() == Some(b'-') {
+                    this.cur += 1;
+                    dash_count += 1;
+                }
+
+                let trailing_colon = this.scan_ch(b':');
+
+                if dash_count == 0 {
+                    return Err(());
+                }
+
+                this.skip_spaces();
+
+                alignments.push(match (leading_colon, trailing_colon) {
+                    (true, true) => Alignment::Center,
+                    (true, false) => Alignment::Left,
+                    (false, true) => Alignment::Right,
+                    (false, false) => Alignment::None,
+                });
+
+                if this.scan_ch(b'|') {
+                    if this.is_at_eol() {
+                        break;
+                    }
+                } else if this.is_at_eol() {
+                    break;
+                } else {
+                    return Err(());
+                }
+            }
+
+            Ok(alignments)
+        })
+        .ok()
+    }
+
+    /// Scan for one of CommonMark's seven HTML-block start conditions.
+    ///
+    /// After up to three leading spaces and a literal `<`, recognizes: (1)
+    /// `<script`, `<pre`, `<style`, or `<textarea`, case-insensitive, followed by
+    /// whitespace, `>`, or EOL; (2) `<!--`; (3) `<?`; (4) `<!` followed by an ASCII
+    /// letter; (5) `<![CDATA[`; (6) an open or close tag for one of the block-level
+    /// `HTML_BLOCK_TAGS` names, terminated by whitespace, `>`, `/>`, or EOL; (7) any other
+    /// complete open or close tag filling the remainder of the line. The returned number
+    /// identifies which condition matched, since conditions 1–6 may interrupt a paragraph
+    /// while condition 7 may not.
+    pub fn scan_html_block_start(&mut self) -> Option<u8> {
+        let start = self.cur;
+
+        if self.html_scan_guard.already_failed(start) {
+            return None;
+        }
+
+        let result = self
+            .try_scan(|this| {
+                this.scan_space(3);
+
+                if !this.scan_ch(b'<') {
+                    return Err(());
+                }
+
+                for tag in [b"script".as_slice(), b"pre", b"style", b"textarea"] {
+                    if this.looks_like_cond1_tag(tag) {
+                        this.cur += tag.len();
+                        return Ok(1);
+                    }
+                }
+
+                if this.scan_bytes(b"!--") {
+                    return Ok(2);
+                }
+
+                if this.scan_ch(b'?') {
+                    return Ok(3);
+                }
+
+                if this.scan_bytes(b"![CDATA[") {
+                    return Ok(5);
+                }
+
+                if this.peek() == Some(b'!') {
+                    this.cur += 1;
+
+                    return if matches!(this.peek(), Some(c) if c.is_ascii_alphabetic()) {
+                        Ok(4)
+                    } else {
+                        Err(())
+                    };
+                }
+
+                this.scan_ch(b'/');
+
+                let name_start = this.cur;
+
+                if !matches!(this.peek(), Some(c) if c.is_ascii_alphabetic()) {
+                    return Err(());
+                }
+
+                this.cur += 1;
+
+                while matches!(this.peek(), Some(c) if c.is_ascii_alphanumeric() || c == b'-') {
+                    this.cur += 1;
+                }
+
+                let name = &this.bytes[name_start..this.cur];
+
+                if is_html_block_tag(name) && this.at_tag_name_terminator() {
+                    return Ok(6);
+                }
+
+                this.scan_html_tag_rest().map(|()| 7)
+            })
+            .ok();
+
+        if result.is_none() {
+            self.html_scan_guard.record_failure(start);
+        }
+
+        result
+    }
+
+    /// Whether the cursor sits just after a tag name that is immediately followed by
+    /// whitespace, `>`, `/>`, or EOL, as required by start condition 6.
+    fn at_tag_name_terminator(&self) -> bool {
+        match self.peek() {
+            Some(b' ' | b'\t' | b'>' | b'\r' | b'\n') | None => true,
+            Some(b'/') => self.bytes.get(self.cur + 1) == Some(&b'>'),
+            _ => false,
+        }
+    }
+
+    /// Whether the cursor sits just before one of the condition-1 literal tag names
+    /// (`script`/`pre`/`style`/`textarea`), case-insensitively, immediately followed by
+    /// whitespace, `>`, or EOL. Unlike [`Self::at_tag_name_terminator`], this excludes
+    /// `/`: a self-closing `<pre/>` is a complete tag on its own (condition 7), not a
+    /// condition-1 opener. Doesn't consume on a `false` result, so a name that matches
+    /// the literal but not the terminator (like `pre` in `<pre/>`) is left for the
+    /// general tag-name scan below to pick up in full.
+    fn looks_like_cond1_tag(&self, tag: &[u8]) -> bool {
+        let end = self.cur + tag.len();
+
+        if end > self.bytes.len() || !self.bytes[self.cur..end].eq_ignore_ascii_case(tag) {
+            return false;
+        }
+
+        matches!(self.bytes.get(end), Some(b' ' | b'\t' | b'>' | b'\r' | b'\n') | None)
+    }
+
+    /// Scan the remainder of a complete HTML open or close tag (attributes, optional `/`,
+    /// `>`), requiring only whitespace from there to EOL, per start condition 7.
+    fn scan_html_tag_rest(&mut self) -> Result<(), ()> {
+        loop {
+            let before = self.cur;
+
+            self.skip_spaces();
+
+            if self.cur == before || !matches!(self.peek(), Some(c) if c.is_ascii_alphabetic() || c == b'_' || c == b':') {
+                break;
+            }
+
+            self.cur += 1;
+
+            while matches!(self.peek(), Some(c) if c.is_ascii_alphanumeric() || matches!(c, b'_' | b':' | b'.' | b'-'))
+            {
+                self.cur += 1;
+            }
+
+            let after_name = self.cur;
+
+            self.skip_spaces();
+
+            if !self.scan_ch(b'=') {
+                // A valueless attribute: leave the whitespace after it for the next one.
+                self.cur = after_name;
+            } else {
+                self.skip_spaces();
+
+                match self.peek() {
+                    Some(quote @ b'"') | Some(quote @ b'\'') => {
+                        self.cur += 1;
+
+                        loop {
+                            match self.peek() {
+                                Some(c) if c == quote => {
+                                    self.cur += 1;
+                                    break;
+                                }
+                                Some(_) => self.cur += 1,
+                                None => return Err(()),
+                            }
+                        }
+                    }
+                    Some(c)
+                        if !matches!(c, b'"' | b'\'' | b'=' | b'<' | b'>' | b'`')
+                            && !c.is_ascii_whitespace() =>
+                    {
+                        while matches!(self.peek(), Some(c) if !c.is_ascii_whitespace() && !matches!(c, b'"' | b'\'' | b'=' | b'<' | b'>' | b'`'))
+                        {
+                            self.cur += 1;
+                        }
+                    }
+                    _ => return Err(()),
+                }
+            }
+        }
+
+        self.scan_ch(b'/');
+
+        if !self.scan_ch(b'>') {
+            return Err(());
+        }
+
+        self.skip_spaces();
+
+        if self.is_at_eol() {
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+
+    fn scan_bytes(&mut self, s: &[u8]) -> bool {
+        let end = self.cur + s.len();
+
+        if end > self.bytes.len() || &self.bytes[self.cur..end] != s {
+            return false;
+        }
+
+        self.cur = end;
+
+        true
+    }
+
+    /// Scan an HTML character entity or numeric character reference, with the cursor
+    /// positioned just after the leading `&`.
+    ///
+    /// Recognizes named entities (`amp;` → `&`, looked up via [`entities::lookup`]),
+    /// decimal numeric references (`#1234;`), and hex numeric references (`#x1F600;` /
+    /// `#X1F600;`). Out-of-range or disallowed code points (null, surrogates, anything
+    /// past `U+10FFFF`) are clamped to the replacement character. Returns the number of
+    /// bytes consumed after the `&` and the decoded text — borrowed for named entities
+    /// that resolve straight out of the static table, owned for numeric references, since
+    /// some named entities (e.g. `&nge;`, `&fjlig;`) decode to more than one code point
+    /// and so can't be represented as a single `char`.
+    pub fn scan_entity(&mut self) -> Option<(usize, Cow<'static, str>)> {
+        self.try_scan(|this| {
+            let start = this.cur;
+
+            if this.scan_ch(b'#') {
+                let value = if matches!(this.peek(), Some(b'x') | Some(b'X')) {
+                    this.cur += 1;
+                    this.scan_hex_digits(6)?
+                } else {
+                    this.scan_decimal_digits(7)?
+                };
+
+                if !this.scan_ch(b';') {
+                    return Err(());
+                }
+
+                let ch = decode_code_point(value);
+
+                return Ok((this.cur - start, Cow::Owned(ch.to_string())));
+            }
+
+            let name_start = this.cur;
+
+            while matches!(this.peek(), Some(c) if c.is_ascii_alphanumeric()) {
+                this.cur += 1;
+            }
+
+            if this.cur == name_start || !this.scan_ch(b';') {
+                return Err(());
+            }
+
+            let name = &this.bytes[name_start..this.cur - 1];
+
+            match entities::lookup(name) {
+                Some(text) => Ok((this.cur - start, Cow::Borrowed(text))),
+                None => Err(()),
+            }
+        })
+        .ok()
+    }
+
+    fn scan_decimal_digits(&mut self, max_digits: usize) -> Result<u32, ()> {
+        let start = self.cur;
+        let mut value: u32 = 0;
+
+        while matches!(self.peek(), Some(c) if c.is_ascii_digit()) && self.cur - start < max_digits {
+            value = value.saturating_mul(10).saturating_add(u32::from(self.next() - b'0'));
+        }
+
+        if self.cur == start {
+            Err(())
+        } else {
+            Ok(value)
+        }
+    }
+
+    fn scan_hex_digits(&mut self, max_digits: usize) -> Result<u32, ()> {
+        let start = self.cur;
+        let mut value: u32 = 0;
+
+        while matches!(self.peek(), Some(c) if c.is_ascii_hexdigit()) && self.cur - start < max_digits {
+            let digit = (self.next() as char).to_digit(16).unwrap();
+            value = value.saturating_mul(16).saturating_add(digit);
+        }
+
+        if self.cur == start {
+            Err(())
+        } else {
+            Ok(value)
+        }
+    }
+
+    /// Scan a CommonMark autolink, with the cursor positioned just after the leading `<`.
+    ///
+    /// Tries a URI autolink first — a scheme of 2–32 ASCII letters/digits/`+`/`.`/`-`,
+    /// `:`, then any run of characters excluding whitespace, `<`, and `>` — falling back
+    /// to an email autolink (`user@domain` per the CommonMark email production) if that
+    /// doesn't match. Both forms are terminated by `>`. Returns the total byte length
+    /// consumed, including the closing `>`, and which form matched; wrapped in `try_scan`
+    /// so a malformed `<...>` is left for the caller to treat as raw text or an HTML tag.
+    pub fn scan_autolink(&mut self) -> Option<(usize, LinkType)> {
+        self.try_scan(|this| {
+            let start = this.cur;
+
+            if let Some(len) = this.scan_uri_autolink_rest() {
+                return Ok((len, LinkType::Autolink));
+            }
+
+            this.cur = start;
+
+            this.scan_email_autolink_rest()
+                .map(|len| (len, LinkType::Email))
+                .ok_or(())
+        })
+        .ok()
+    }
+
+    fn scan_uri_autolink_rest(&mut self) -> Option<usize> {
+        let start = self.cur;
+        let scheme_start = self.cur;
+
+        if !matches!(self.peek(), Some(c) if c.is_ascii_alphabetic()) {
+            return None;
+        }
+
+        self.cur += 1;
+
+        while matches!(self.peek(), Some(c) if c.is_ascii_alphanumeric() || matches!(c, b'+' | b'.' | b'-'))
+        {
+            self.cur += 1;
+        }
+
+        if !(2..=32).contains(&(self.cur - scheme_start)) {
+            return None;
+        }
+
+        if !self.scan_ch(b':') {
+            return None;
+        }
+
+        while matches!(self.peek(), Some(c) if !c.is_ascii_whitespace() && !matches!(c, b'<' | b'>'))
+        {
+            self.cur += 1;
+        }
+
+        if !self.scan_ch(b'>') {
+            return None;
+        }
+
+        Some(self.cur - start)
+    }
+
+    fn scan_email_autolink_rest(&mut self) -> Option<usize> {
+        let start = self.cur;
+
+        if !matches!(self.peek(), Some(c) if is_email_local_char(c)) {
+            return None;
+        }
+
+        while matches!(self.peek(), Some(c) if is_email_local_char(c)) {
+            self.cur += 1;
+        }
+
+        if !self.scan_ch(b'@') {
+            return None;
+        }
+
+        if !self.scan_email_domain_label() {
+            return None;
+        }
+
+        while self.peek() == Some(b'.') {
+            self.cur += 1;
+
+            if !self.scan_email_domain_label() {
+                return None;
+            }
+        }
+
+        if !self.scan_ch(b'>') {
+            return None;
+        }
+
+        Some(self.cur - start)
+    }
+
+    /// Scan a single dot-separated domain label: an alphanumeric, then up to 61
+    /// alphanumerics/hyphens, ending on an alphanumeric.
+    fn scan_email_domain_label(&mut self) -> bool {
+        if !matches!(self.peek(), Some(c) if c.is_ascii_alphanumeric()) {
+            return false;
+        }
+
+        let start = self.cur;
+        self.cur += 1;
+
+        while matches!(self.peek(), Some(c) if c.is_ascii_alphanumeric() || c == b'-')
+            && self.cur - start < 63
+        {
+            self.cur += 1;
+        }
+
+        while self.bytes[self.cur - 1] == b'-' {
+            self.cur -= 1;
+        }
+
+        true
+    }
+
     /// Scan a list marker.
     ///
     /// Return value is the character and the start index.
@@ -114,6 +803,10 @@ impl<'a> LineStart<'a> {
                     this.cur += 1;
 
                     if this.scan_space(1) == 1 || this.is_at_eol() {
+                        // A `-` marker already claimed as a bullet list marker up to this
+                        // column must not also be read as the start of a thematic break.
+                        this.min_hrule_offset = this.min_hrule_offset.max(this.cur);
+
                         Ok((ch, 0))
                     } else {
                         Err(())
@@ -181,6 +874,51 @@ impl<'a> LineStart<'a> {
         .ok()
     }
 
+    /// Scan a thematic break (hrule) line.
+    ///
+    /// After up to three leading spaces, a thematic break is three or more of a single
+    /// repeated `-`, `_`, or `*`, with any number of interior spaces/tabs, and nothing
+    /// else before the end of the line. `min_hrule_offset` records the column at which a
+    /// hrule run is still allowed to start: a `-` can equally be read as a bullet-list
+    /// marker, so once one has been consumed as such at a given column, setting
+    /// `min_hrule_offset` past it keeps a later rescan of the same line from also
+    /// reporting it as a break.
+    pub fn scan_hrule(&mut self) -> bool {
+        self.try_scan(|this| {
+            this.scan_space(3);
+
+            if this.cur < this.min_hrule_offset {
+                return Err(());
+            }
+
+            let ch = match this.peek() {
+                Some(c) if matches!(c, b'-' | b'_' | b'*') => c,
+                _ => return Err(()),
+            };
+
+            let mut count = 0;
+
+            loop {
+                match this.peek() {
+                    Some(c) if c == ch => {
+                        this.cur += 1;
+                        count += 1;
+                    }
+                    Some(b' ') | Some(b'\t') => this.cur += 1,
+                    _ => break,
+                }
+            }
+
+            if count >= 3 && this.is_at_eol() {
+                this.min_hrule_offset = this.cur;
+                Ok(true)
+            } else {
+                Err(())
+            }
+        })
+        .unwrap_or(false)
+    }
+
     fn try_scan<F, R, E>(&mut self, mut scan: F) -> Result<R, E>
     where
         F: FnMut(&mut Self) -> Result<R, E>,
@@ -196,3 +934,166 @@ impl<'a> LineStart<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scan_space_consumes_plain_spaces() {
+        let mut ls = LineStart::new(b"   abc");
+        assert_eq!(ls.scan_space(3), 3);
+        assert_eq!(ls.peek(), Some(b'a'));
+    }
+
+    #[test]
+    fn scan_space_stops_short_of_available_input() {
+        let mut ls = LineStart::new(b"  abc");
+        assert_eq!(ls.scan_space(3), 2);
+        assert_eq!(ls.peek(), Some(b'a'));
+    }
+
+    #[test]
+    fn peek_reads_the_byte_at_the_cursor() {
+        let mut ls = LineStart::new(b"ab");
+        assert_eq!(ls.peek(), Some(b'a'));
+        ls.next();
+        assert_eq!(ls.peek(), Some(b'b'));
+    }
+
+    #[test]
+    fn scan_hrule_recognizes_spaced_dashes() {
+        let mut ls = LineStart::new(b"- - -");
+        assert!(ls.scan_hrule());
+    }
+
+    #[test]
+    fn scan_hrule_rejects_too_few_chars() {
+        let mut ls = LineStart::new(b"--");
+        assert!(!ls.scan_hrule());
+    }
+
+    #[test]
+    fn list_marker_raises_min_hrule_offset_past_the_marker() {
+        let mut ls = LineStart::new(b"- - -");
+        assert_eq!(ls.scan_list_marker(), Some((b'-', 0)));
+        assert!(ls.min_hrule_offset >= 2);
+    }
+
+    #[test]
+    fn scan_code_fence_reports_char_len_and_indent() {
+        let mut ls = LineStart::new(b"  ```rust");
+        assert_eq!(ls.scan_code_fence(), Some((b'`', 3, 2)));
+    }
+
+    #[test]
+    fn scan_code_fence_rejects_backtick_in_info_string() {
+        let mut ls = LineStart::new(b"```a`b");
+        assert_eq!(ls.scan_code_fence(), None);
+    }
+
+    #[test]
+    fn scan_closing_fence_requires_at_least_opening_length() {
+        let mut ls = LineStart::new(b"~~~~");
+        assert!(ls.scan_closing_fence(b'~', 3));
+    }
+
+    #[test]
+    fn scan_atx_heading_returns_level() {
+        let mut ls = LineStart::new(b"### heading");
+        assert_eq!(ls.scan_atx_heading(), Some(3));
+    }
+
+    #[test]
+    fn scan_atx_heading_rejects_missing_separator() {
+        let mut ls = LineStart::new(b"###heading");
+        assert_eq!(ls.scan_atx_heading(), None);
+    }
+
+    #[test]
+    fn scan_setext_underline_detects_level() {
+        let mut ls = LineStart::new(b"===");
+        assert_eq!(ls.scan_setext_underline(), Some(1));
+    }
+
+    #[test]
+    fn scan_table_delimiter_row_reports_alignment() {
+        let mut ls = LineStart::new(b"| :--- | :--: | ---: |");
+        assert_eq!(
+            ls.scan_table_delimiter_row(),
+            Some(vec![Alignment::Left, Alignment::Center, Alignment::Right])
+        );
+    }
+
+    #[test]
+    fn scan_table_delimiter_row_rejects_empty_cell() {
+        let mut ls = LineStart::new(b"| --- |  |");
+        assert_eq!(ls.scan_table_delimiter_row(), None);
+    }
+
+    #[test]
+    fn scan_html_block_start_detects_script_tag() {
+        let mut ls = LineStart::new(b"<script>");
+        assert_eq!(ls.scan_html_block_start(), Some(1));
+    }
+
+    #[test]
+    fn scan_html_block_start_self_closing_pre_is_condition_seven_not_one() {
+        let mut ls = LineStart::new(b"<pre/>");
+        assert_eq!(ls.scan_html_block_start(), Some(7));
+    }
+
+    #[test]
+    fn scan_html_block_start_allows_hyphenated_tag_names() {
+        let mut ls = LineStart::new(b"<custom-tag>");
+        assert_eq!(ls.scan_html_block_start(), Some(7));
+    }
+
+    #[test]
+    fn scan_entity_decodes_named_entity() {
+        let input: &[u8] = b"amp;rest";
+        let mut ls = LineStart::new(input);
+        assert_eq!(ls.scan_entity(), Some((4, Cow::Borrowed("&"))));
+    }
+
+    #[test]
+    fn scan_entity_decodes_multi_codepoint_named_entity() {
+        let input: &[u8] = b"fjlig;";
+        let mut ls = LineStart::new(input);
+        assert_eq!(ls.scan_entity(), Some((input.len(), Cow::Borrowed("fj"))));
+    }
+
+    #[test]
+    fn scan_entity_decodes_hex_numeric_reference() {
+        let input: &[u8] = b"#x1F600;";
+        let mut ls = LineStart::new(input);
+        assert_eq!(
+            ls.scan_entity(),
+            Some((input.len(), Cow::Owned('\u{1F600}'.to_string())))
+        );
+    }
+
+    #[test]
+    fn scan_entity_clamps_disallowed_code_point() {
+        let input: &[u8] = b"#xD800;";
+        let mut ls = LineStart::new(input);
+        assert_eq!(
+            ls.scan_entity(),
+            Some((input.len(), Cow::Owned('\u{FFFD}'.to_string())))
+        );
+    }
+
+    #[test]
+    fn scan_autolink_detects_uri() {
+        let input: &[u8] = b"https://example.com>";
+        let mut ls = LineStart::new(input);
+        assert_eq!(ls.scan_autolink(), Some((input.len(), LinkType::Autolink)));
+    }
+
+    #[test]
+    fn scan_autolink_detects_email() {
+        let input: &[u8] = b"foo@bar.com>";
+        let mut ls = LineStart::new(input);
+        assert_eq!(ls.scan_autolink(), Some((input.len(), LinkType::Email)));
+    }
+}